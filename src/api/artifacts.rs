@@ -0,0 +1,253 @@
+//! Streamed artifact upload/download, storing objects in GCS rather than
+//! inline on the `Task` document. Uploads are authenticated with the shared
+//! worker key (see `auth::AuthenticatedWorker`), since a worker has no way to
+//! hold a per-user JWT for the task's owner, and are accepted only when the
+//! `worker_id` in the query matches the task's `claimed_by`; downloads are
+//! authenticated as the owning end user fetching their own results.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use axum::body::Body;
+use axum::extract::{BodyStream, Path, Query, State};
+use axum::http::{header, StatusCode};
+use axum::response::IntoResponse;
+use axum::Json;
+use firestore::paths;
+use futures_util::TryStreamExt;
+use google_cloud_storage::http::objects::download::Range;
+use google_cloud_storage::http::objects::get::GetObjectRequest;
+use google_cloud_storage::http::objects::upload::{Media, UploadObjectRequest, UploadType};
+use log::{error, info};
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::auth::{AuthenticatedUser, AuthenticatedWorker};
+use crate::models::{ArtifactRef, Task};
+use crate::ApiState;
+
+/// Hard cap on a single artifact's size, enforced while streaming so an
+/// oversized upload is rejected before it's fully buffered.
+const MAX_ARTIFACT_BYTES: u64 = 5 * 1024 * 1024 * 1024;
+
+#[derive(Debug, Deserialize)]
+pub struct ArtifactQuery {
+    pub worker_id: String,
+}
+
+pub async fn upload_artifact(
+    State(state): State<ApiState>,
+    Path((user_id, task_id, artifact_name)): Path<(String, String, String)>,
+    Query(query): Query<ArtifactQuery>,
+    _worker: AuthenticatedWorker,
+    headers: axum::http::HeaderMap,
+    body: BodyStream,
+) -> impl IntoResponse {
+    let parent_path = match state.firestore_client.parent_path("users", user_id.clone()) {
+        Ok(path) => path,
+        Err(e) => {
+            error!("Failed to get parent path: {}", e);
+            return Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": format!("Failed to get parent path: {}", e)})),
+            ));
+        }
+    };
+
+    let task = match state
+        .firestore_client
+        .fluent()
+        .select()
+        .by_id_in("tasks")
+        .parent(parent_path.clone())
+        .obj::<Task>()
+        .one(&task_id)
+        .await
+    {
+        Ok(Some(task)) => task,
+        Ok(None) => {
+            return Err((
+                StatusCode::NOT_FOUND,
+                Json(json!({"error": format!("Failed to find task: {}", task_id)})),
+            ));
+        }
+        Err(e) => {
+            error!("Database error: {}", e);
+            return Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": format!("Failed to get task: {}", e)})),
+            ));
+        }
+    };
+
+    if task.claimed_by.as_deref() != Some(query.worker_id.as_str()) {
+        return Err((
+            StatusCode::FORBIDDEN,
+            Json(json!({"error": "Task is not claimed by this worker"})),
+        ));
+    }
+
+    let content_type = headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("application/octet-stream")
+        .to_string();
+
+    let gcs_path = format!("{}/{}/{}", user_id, task_id, artifact_name);
+
+    // Tracks bytes seen so far; shared with the stream adapter below so we
+    // can both enforce the size cap mid-upload and record the final size.
+    let bytes_seen = Arc::new(AtomicU64::new(0));
+    let limited_stream = {
+        let bytes_seen = bytes_seen.clone();
+        body.map_err(std::io::Error::other).and_then(move |chunk| {
+            let total = bytes_seen.fetch_add(chunk.len() as u64, Ordering::Relaxed) + chunk.len() as u64;
+            if total > MAX_ARTIFACT_BYTES {
+                futures_util::future::err(std::io::Error::other("artifact exceeds max size"))
+            } else {
+                futures_util::future::ok(chunk)
+            }
+        })
+    };
+
+    let upload_type = UploadType::Simple(Media {
+        name: gcs_path.clone().into(),
+        content_type: content_type.clone().into(),
+        content_length: None,
+    });
+
+    if let Err(e) = state
+        .gcs_client
+        .upload_streamed_object(
+            &UploadObjectRequest {
+                bucket: state.gcs_bucket.to_string(),
+                ..Default::default()
+            },
+            limited_stream,
+            &upload_type,
+        )
+        .await
+    {
+        error!("Failed to upload artifact {}: {}", gcs_path, e);
+        return Err((
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({"error": format!("Failed to upload artifact: {}", e)})),
+        ));
+    }
+
+    let artifact = ArtifactRef {
+        name: artifact_name,
+        size: bytes_seen.load(Ordering::Relaxed),
+        content_type,
+        gcs_path,
+    };
+
+    let mut artifacts = task.artifacts.clone();
+    artifacts.retain(|a| a.name != artifact.name);
+    artifacts.push(artifact.clone());
+
+    if let Err(e) = state
+        .firestore_client
+        .fluent()
+        .update()
+        .fields(paths!(Task::{artifacts}))
+        .in_col("tasks")
+        .document_id(&task_id)
+        .parent(parent_path)
+        .object(&Task {
+            artifacts: artifacts.clone(),
+            ..task
+        })
+        .execute::<Task>()
+        .await
+    {
+        error!("Failed to record artifact manifest for task {}: {}", task_id, e);
+        return Err((
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({"error": format!("Failed to record artifact manifest: {}", e)})),
+        ));
+    }
+
+    info!("Worker {} uploaded artifact {:?} for task {}", query.worker_id, artifact, task_id);
+
+    Ok((StatusCode::CREATED, Json(json!(artifact))))
+}
+
+pub async fn download_artifact(
+    State(state): State<ApiState>,
+    Path((user_id, task_id, artifact_name)): Path<(String, String, String)>,
+    auth: AuthenticatedUser,
+) -> impl IntoResponse {
+    auth.authorize(&user_id)?;
+
+    let parent_path = match state.firestore_client.parent_path("users", user_id.clone()) {
+        Ok(path) => path,
+        Err(e) => {
+            error!("Failed to get parent path: {}", e);
+            return Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": format!("Failed to get parent path: {}", e)})),
+            ));
+        }
+    };
+
+    let task = match state
+        .firestore_client
+        .fluent()
+        .select()
+        .by_id_in("tasks")
+        .parent(parent_path)
+        .obj::<Task>()
+        .one(&task_id)
+        .await
+    {
+        Ok(Some(task)) => task,
+        Ok(None) => {
+            return Err((
+                StatusCode::NOT_FOUND,
+                Json(json!({"error": format!("Failed to find task: {}", task_id)})),
+            ));
+        }
+        Err(e) => {
+            error!("Database error: {}", e);
+            return Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": format!("Failed to get task: {}", e)})),
+            ));
+        }
+    };
+
+    let Some(artifact) = task.artifacts.into_iter().find(|a| a.name == artifact_name) else {
+        return Err((
+            StatusCode::NOT_FOUND,
+            Json(json!({"error": format!("No such artifact: {}", artifact_name)})),
+        ));
+    };
+
+    let stream = match state
+        .gcs_client
+        .download_streamed_object(
+            &GetObjectRequest {
+                bucket: state.gcs_bucket.to_string(),
+                object: artifact.gcs_path.clone(),
+                ..Default::default()
+            },
+            &Range::default(),
+        )
+        .await
+    {
+        Ok(stream) => stream,
+        Err(e) => {
+            error!("Failed to download artifact {}: {}", artifact.gcs_path, e);
+            return Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": format!("Failed to download artifact: {}", e)})),
+            ));
+        }
+    };
+
+    Ok((
+        [(header::CONTENT_TYPE, artifact.content_type.clone())],
+        Body::from_stream(stream),
+    ))
+}
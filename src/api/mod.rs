@@ -0,0 +1,89 @@
+pub mod artifacts;
+pub mod models;
+pub mod tasks;
+pub mod users;
+pub mod workers;
+
+use axum::http::StatusCode;
+use axum::Json;
+use firestore::FirestoreDb;
+use log::error;
+use serde_json::{json, Value};
+
+use crate::api::models::UserDocument;
+use crate::models::Task;
+
+type ApiError = (StatusCode, Json<Value>);
+
+pub async fn does_user_exist(firestore_client: &FirestoreDb, user_id: &str) -> Result<bool, ApiError> {
+    match firestore_client
+        .fluent()
+        .select()
+        .by_id_in("users")
+        .obj::<UserDocument>()
+        .one(user_id)
+        .await
+    {
+        Ok(user) => Ok(user.is_some()),
+        Err(e) => {
+            error!("Failed to check if user exists: {}", e);
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": format!("Failed to check if user exists: {}", e)})),
+            ))
+        }
+    }
+}
+
+pub async fn does_task_exist(
+    firestore_client: &FirestoreDb,
+    task_id: &str,
+    parent_path: &str,
+) -> Result<bool, ApiError> {
+    match firestore_client
+        .fluent()
+        .select()
+        .by_id_in("tasks")
+        .parent(parent_path)
+        .obj::<Task>()
+        .one(task_id)
+        .await
+    {
+        Ok(task) => Ok(task.is_some()),
+        Err(e) => {
+            error!("Failed to check if task exists: {}", e);
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": format!("Failed to check if task exists: {}", e)})),
+            ))
+        }
+    }
+}
+
+pub async fn emit_event(
+    pubsub_client: &google_cloud_pubsub::client::Client,
+    event_name: &str,
+    payload: &str,
+) -> Result<(), ApiError> {
+    let topic = pubsub_client.topic(event_name);
+    let publisher = topic.new_publisher(None);
+
+    match publisher
+        .publish(google_cloud_googleapis::pubsub::v1::PubsubMessage {
+            data: payload.as_bytes().to_vec(),
+            ..Default::default()
+        })
+        .await
+        .get()
+        .await
+    {
+        Ok(_) => Ok(()),
+        Err(e) => {
+            error!("Failed to publish {}: {}", event_name, e);
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": format!("Failed to publish {}: {}", event_name, e)})),
+            ))
+        }
+    }
+}
@@ -0,0 +1,10 @@
+use serde::{Deserialize, Serialize};
+
+/// Firestore-only record for a user; not part of the public task protocol.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UserDocument {
+    pub tasks: Vec<String>,
+    /// Argon2 hash of the user's password, checked by `auth::sign_in`.
+    #[serde(default)]
+    pub password_hash: String,
+}
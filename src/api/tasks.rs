@@ -7,18 +7,28 @@ use log::{debug, error, info};
 use serde_json::json;
 
 use crate::api::{does_task_exist, does_user_exist, emit_event};
+use crate::auth::AuthenticatedUser;
 use crate::ApiState;
-use crate::commands::CreateTaskCommand;
+use crate::commands::{
+    CreateTaskCommand, DEFAULT_BACKOFF_BASE_SECONDS, DEFAULT_MAX_ATTEMPTS, MAX_ALLOWED_ATTEMPTS,
+    MAX_ALLOWED_BACKOFF_BASE_SECONDS,
+};
 use crate::events::{TaskCompletedEvent, TaskCreatedEvent};
 use crate::models::{Task, TaskData, TaskStatus};
 
+/// Upper bound on the computed retry backoff, regardless of attempts.
+const MAX_BACKOFF_SECONDS: i64 = 15 * 60;
+
 pub async fn create_task(
     State(state): State<ApiState>,
+    auth: AuthenticatedUser,
     Json(params): Json<CreateTaskCommand>,
 ) -> impl IntoResponse {
     debug!("Request received: {:#?}", params);
     let user_id = params.user_id.clone();
 
+    auth.authorize(&user_id)?;
+
     // Make sure the user exists
     if !does_user_exist(&state.firestore_client, &user_id).await? {
         return Err((
@@ -55,6 +65,14 @@ pub async fn create_task(
         object_path: params.object_path.clone(),
         created_by: user_id.clone(),
         status: TaskStatus::Queued.into(),
+        max_attempts: params
+            .max_attempts
+            .unwrap_or(DEFAULT_MAX_ATTEMPTS)
+            .clamp(0, MAX_ALLOWED_ATTEMPTS),
+        backoff_base_seconds: params
+            .backoff_base_seconds
+            .unwrap_or(DEFAULT_BACKOFF_BASE_SECONDS)
+            .clamp(0, MAX_ALLOWED_BACKOFF_BASE_SECONDS),
         created_at: Some(prost_wkt_types::Timestamp {
             seconds: chrono::Utc::now().timestamp(),
             nanos: 0,
@@ -112,22 +130,51 @@ pub async fn create_task(
 
 pub async fn task_complete(
     State(state): State<ApiState>,
+    auth: AuthenticatedUser,
     Json(task_completion_event): Json<TaskCompletedEvent>,
 ) -> impl IntoResponse {
     info!("Request received: {:#?}", &task_completion_event);
 
     // Make sure the status is valid
-    if let Err(e) = TaskStatus::try_from(task_completion_event.status.clone()) {
-        error!("Invalid task status: {}", e);
+    let requested_status = match TaskStatus::try_from(task_completion_event.status.clone()) {
+        Ok(status) => status,
+        Err(e) => {
+            error!("Invalid task status: {}", e);
+            return Err((
+                StatusCode::BAD_REQUEST,
+                Json(json!({"status": format!("Invalid task status: {}", e)})),
+            ));
+        }
+    };
+
+    // task_complete is the caller-facing "I'm done" endpoint: it may only
+    // report one of the outcomes a worker can actually finish with.
+    // Queued/Running/Retrying are claim- and reaper-internal transitions
+    // driven by claim_task/task_heartbeat/the reaper, not by this endpoint;
+    // accepting them here would let a caller park a task in `Retrying`
+    // without the `next_run_at` the reaper relies on to ever promote it.
+    if !matches!(
+        requested_status,
+        TaskStatus::Completed | TaskStatus::Failed | TaskStatus::Cancelled
+    ) {
+        error!(
+            "Rejected caller-supplied non-terminal status for task {}: {:?}",
+            task_completion_event.task_id, requested_status
+        );
         return Err((
-            StatusCode::BAD_REQUEST,
-            Json(json!({"status": format!("Invalid task status: {}", e)})),
+            StatusCode::CONFLICT,
+            Json(json!({
+                "status": "task_complete only accepts Completed, Failed, or Cancelled",
+                "attempted_status": requested_status,
+            })),
         ));
     }
 
     // Get the task
     let user_id = task_completion_event.user_id.clone();
 
+    auth.authorize(&user_id)?;
+
     // Make sure the user exists
     if !does_user_exist(&state.firestore_client, &user_id).await? {
         return Err((
@@ -186,21 +233,70 @@ pub async fn task_complete(
         }
     };
 
+    // Make sure the transition is legal before touching anything
+    let current_status = TaskStatus::try_from(task.status).unwrap_or_default();
+    if !current_status.can_transition_to(requested_status) {
+        error!(
+            "Rejected illegal transition for task {}: {:?} -> {:?}",
+            task_completion_event.task_id, current_status, requested_status
+        );
+        return Err((
+            StatusCode::CONFLICT,
+            Json(json!({
+                "status": "Illegal task state transition",
+                "current_status": current_status,
+                "attempted_status": requested_status,
+            })),
+        ));
+    }
+
+    let now = chrono::Utc::now();
+    let started_at = task
+        .claimed_at
+        .as_ref()
+        .or(task.created_at.as_ref())
+        .map(|ts| ts.seconds)
+        .unwrap_or_else(|| now.timestamp());
+    let duration_seconds = (now.timestamp() - started_at).max(0);
+
+    // A `Failed` completion with attempts remaining becomes a scheduled
+    // retry instead of a terminal state.
+    let (final_status, attempts, next_run_at) =
+        if requested_status == TaskStatus::Failed && task.attempts < task.max_attempts {
+            let attempts = task.attempts + 1;
+            let backoff = task
+                .backoff_base_seconds
+                .saturating_mul(2i64.saturating_pow(attempts.max(0) as u32))
+                .min(MAX_BACKOFF_SECONDS);
+            (
+                TaskStatus::Retrying,
+                attempts,
+                Some(prost_wkt_types::Timestamp {
+                    seconds: now.timestamp() + backoff,
+                    nanos: 0,
+                }),
+            )
+        } else {
+            (requested_status, task.attempts, None)
+        };
+
     // Update the task
     let updated = match state
         .firestore_client
         .fluent()
         .update()
-        .fields(paths!(Task::{status, result, duration_seconds, updated_at, last_publish_time}))
+        .fields(paths!(Task::{status, result, duration_seconds, attempts, next_run_at, updated_at, last_publish_time}))
         .in_col("tasks")
         .document_id(&task_completion_event.task_id)
         .parent(parent_path)
         .object(&Task {
-            status: task_completion_event.status.clone(),
+            status: final_status.into(),
             result: task_completion_event.result.clone(),
-            duration_seconds: 0,
+            duration_seconds,
+            attempts,
+            next_run_at,
             updated_at: Some(prost_wkt_types::Timestamp {
-                seconds: chrono::Utc::now().timestamp(),
+                seconds: now.timestamp(),
                 nanos: 0,
             }),
             last_publish_time: None,
@@ -219,6 +315,24 @@ pub async fn task_complete(
         }
     };
 
+    // Only a genuinely terminal outcome is announced; a scheduled retry is
+    // promoted back to `Queued` (and re-announced then) by the reaper.
+    if matches!(
+        final_status,
+        TaskStatus::Completed | TaskStatus::Failed | TaskStatus::Cancelled
+    ) {
+        emit_event(
+            &state.pubsub_client,
+            "TaskCompletedEvent",
+            &serde_json::to_string(&TaskCompletedEvent {
+                artifacts: updated.artifacts.clone(),
+                ..task_completion_event.clone()
+            })
+            .unwrap(),
+        )
+        .await?;
+    }
+
     return Ok((
         StatusCode::OK,
         Json(json!({"status": "Task updated", "task": updated})),
@@ -1,7 +1,10 @@
+use argon2::password_hash::{rand_core::OsRng, PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use argon2::Argon2;
 use axum::extract::State;
 use axum::http::StatusCode;
 use axum::Json;
 use axum::response::IntoResponse;
+use firestore::FirestoreDb;
 use log::{debug, error};
 use serde_json::json;
 
@@ -10,12 +13,18 @@ use crate::api::models::UserDocument;
 use crate::ApiState;
 use crate::events::{UserCreatedEvent};
 
+/// Sign-up is inherently a pre-auth operation: a caller can't hold a token
+/// for a user that doesn't exist yet, so this endpoint takes no
+/// `AuthenticatedUser`. The password set here is what `auth::sign_in` later
+/// checks to mint one.
 pub async fn create_user(
     State(state): State<ApiState>,
     Json(params): Json<UserCreatedEvent>,
 ) -> impl IntoResponse {
     debug!("Request received: {:#?}", params);
-    let user_id = params.user.unwrap().user_id;
+    let user = params.user.unwrap();
+    let user_id = user.user_id;
+
     // Make sure the user exists
     if does_user_exist(&state.firestore_client, &user_id).await? {
         return Err((
@@ -24,6 +33,14 @@ pub async fn create_user(
         ));
     };
 
+    let password_hash = hash_password(&user.password).map_err(|e| {
+        error!("Failed to hash password: {}", e);
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({"status": "Failed to create user"})),
+        )
+    })?;
+
     // Place the user in the DB. This should just be the user ID
     if let Err(e) = state
         .firestore_client
@@ -31,7 +48,10 @@ pub async fn create_user(
         .insert()
         .into("users")
         .document_id(&user_id)
-        .object::<UserDocument>(&UserDocument { tasks: vec![] })
+        .object::<UserDocument>(&UserDocument {
+            tasks: vec![],
+            password_hash,
+        })
         .execute::<UserDocument>()
         .await
     {
@@ -46,4 +66,46 @@ pub async fn create_user(
         StatusCode::CREATED,
         Json(json!({"status": "User created successfully"})),
     ));
+}
+
+fn hash_password(password: &str) -> Result<String, argon2::password_hash::Error> {
+    let salt = SaltString::generate(&mut OsRng);
+    Ok(Argon2::default()
+        .hash_password(password.as_bytes(), &salt)?
+        .to_string())
+}
+
+/// Checks a plaintext password against the stored hash for `user_id`, used
+/// by `auth::sign_in` to decide whether to issue a token.
+pub async fn verify_credentials(
+    firestore_client: &FirestoreDb,
+    user_id: &str,
+    password: &str,
+) -> Result<bool, (StatusCode, Json<serde_json::Value>)> {
+    let user = match firestore_client
+        .fluent()
+        .select()
+        .by_id_in("users")
+        .obj::<UserDocument>()
+        .one(user_id)
+        .await
+    {
+        Ok(Some(user)) => user,
+        Ok(None) => return Ok(false),
+        Err(e) => {
+            error!("Failed to look up user: {}", e);
+            return Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": format!("Failed to look up user: {}", e)})),
+            ));
+        }
+    };
+
+    let Ok(parsed_hash) = PasswordHash::new(&user.password_hash) else {
+        return Ok(false);
+    };
+
+    Ok(Argon2::default()
+        .verify_password(password.as_bytes(), &parsed_hash)
+        .is_ok())
 }
\ No newline at end of file
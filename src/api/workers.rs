@@ -0,0 +1,317 @@
+use std::time::Duration;
+
+use axum::extract::{Query, State};
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use axum::Json;
+use firestore::{paths, path};
+use log::{debug, error, info, warn};
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::api::emit_event;
+use crate::auth::AuthenticatedWorker;
+use crate::commands::{ClaimTaskCommand, HeartbeatCommand};
+use crate::events::TaskClaimedEvent;
+use crate::models::{Task, TaskStatus};
+use crate::ApiState;
+
+/// How often an idle long-poll re-checks Firestore for new `Queued` tasks.
+const CLAIM_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Number of `Queued` candidates pulled per poll, so a single loser doesn't
+/// require a full extra round trip to try the next task in line.
+const CLAIM_BATCH_SIZE: u32 = 5;
+
+#[derive(Debug, Deserialize)]
+pub struct ClaimTaskQuery {
+    /// Long-poll budget: block until a task is available or this elapses.
+    pub wait_seconds: Option<u64>,
+}
+
+/// A worker asks for exactly one `Queued` task, atomically transitioning it
+/// to `Running` and stamping `claimed_by`/`claimed_at`. When `wait_seconds`
+/// is set and no task is immediately available, the handler polls Firestore
+/// on an interval instead of making idle workers busy-loop against us.
+pub async fn claim_task(
+    State(state): State<ApiState>,
+    _worker: AuthenticatedWorker,
+    Query(query): Query<ClaimTaskQuery>,
+    Json(params): Json<ClaimTaskCommand>,
+) -> impl IntoResponse {
+    debug!("Worker {} requesting a task: {:#?}", params.worker_id, params);
+
+    let deadline = query
+        .wait_seconds
+        .map(|secs| tokio::time::Instant::now() + Duration::from_secs(secs));
+
+    loop {
+        match try_claim_one(&state, &params).await {
+            Ok(Some(task)) => {
+                info!("Worker {} claimed task {}", params.worker_id, task.task_id);
+                return Ok((StatusCode::OK, Json(json!(task))));
+            }
+            // Either no Queued candidates, or we lost the race for every
+            // candidate in this batch to other workers. Both are "try again
+            // later" for the purposes of long-polling, not a hard failure.
+            Ok(None) => {}
+            Err((StatusCode::CONFLICT, _)) => {}
+            Err(e) => return Err(e),
+        }
+
+        match deadline {
+            Some(deadline) if tokio::time::Instant::now() < deadline => {
+                tokio::time::sleep(CLAIM_POLL_INTERVAL).await;
+            }
+            _ => {
+                return Err((
+                    StatusCode::NOT_FOUND,
+                    Json(json!({"error": "No queued tasks available"})),
+                ));
+            }
+        }
+    }
+}
+
+/// Pulls a small batch of `Queued` candidates and tries to win each one in
+/// turn via a Firestore transaction, so a single lost race doesn't force the
+/// caller to wait out a whole poll interval before trying again.
+async fn try_claim_one(
+    state: &ApiState,
+    params: &ClaimTaskCommand,
+) -> Result<Option<Task>, (StatusCode, Json<serde_json::Value>)> {
+    let candidates = match state
+        .firestore_client
+        .fluent()
+        .select()
+        .from("tasks")
+        .all_descendants()
+        .filter(|q| q.for_all([q.field(path!(Task::status)).eq(i32::from(TaskStatus::Queued))]))
+        .limit(CLAIM_BATCH_SIZE)
+        .obj::<Task>()
+        .query()
+        .await
+    {
+        Ok(tasks) => tasks,
+        Err(e) => {
+            error!("Failed to query queued tasks: {}", e);
+            return Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": format!("Failed to query queued tasks: {}", e)})),
+            ));
+        }
+    };
+
+    let mut lost_race = false;
+    for candidate in &candidates {
+        match claim_in_transaction(state, candidate, params).await? {
+            Some(task) => return Ok(Some(task)),
+            None => lost_race = true,
+        }
+    }
+
+    if lost_race {
+        return Err((
+            StatusCode::CONFLICT,
+            Json(json!({"error": "Another worker claimed the candidate tasks first"})),
+        ));
+    }
+
+    Ok(None)
+}
+
+/// Reads `candidate` inside a Firestore transaction and, only if it is still
+/// `Queued`, conditionally writes it to `Running` with the claim stamped on
+/// it. Returns `Ok(None)` if another worker won the race in between.
+async fn claim_in_transaction(
+    state: &ApiState,
+    candidate: &Task,
+    params: &ClaimTaskCommand,
+) -> Result<Option<Task>, (StatusCode, Json<serde_json::Value>)> {
+    let parent_path = match state
+        .firestore_client
+        .parent_path("users", candidate.created_by.clone())
+    {
+        Ok(path) => path,
+        Err(e) => {
+            error!("Failed to get parent path: {}", e);
+            return Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": format!("Failed to get parent path: {}", e)})),
+            ));
+        }
+    };
+
+    let mut transaction = match state.firestore_client.begin_transaction().await {
+        Ok(t) => t,
+        Err(e) => {
+            error!("Failed to begin transaction: {}", e);
+            return Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": format!("Failed to begin transaction: {}", e)})),
+            ));
+        }
+    };
+
+    let current = match state
+        .firestore_client
+        .fluent()
+        .select()
+        .by_id_in("tasks")
+        .parent(parent_path.clone())
+        .obj::<Task>()
+        .one(&candidate.task_id)
+        .add_to_transaction(&mut transaction)
+        .await
+    {
+        Ok(Some(task)) => task,
+        Ok(None) => return Ok(None),
+        Err(e) => {
+            error!("Failed to read task in transaction: {}", e);
+            return Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": format!("Failed to read task: {}", e)})),
+            ));
+        }
+    };
+
+    if current.status != i32::from(TaskStatus::Queued) {
+        // Another worker already moved this task out from under us.
+        return Ok(None);
+    }
+
+    let now = Some(prost_wkt_types::Timestamp {
+        seconds: chrono::Utc::now().timestamp(),
+        nanos: 0,
+    });
+    let claimed = Task {
+        status: TaskStatus::Running.into(),
+        claimed_by: Some(params.worker_id.clone()),
+        claimed_at: now.clone(),
+        last_heartbeat: now.clone(),
+        updated_at: now,
+        ..current
+    };
+
+    if let Err(e) = state
+        .firestore_client
+        .fluent()
+        .update()
+        .fields(paths!(Task::{status, claimed_by, claimed_at, last_heartbeat, updated_at}))
+        .in_col("tasks")
+        .document_id(&candidate.task_id)
+        .parent(parent_path)
+        .object(&claimed)
+        .add_to_transaction(&mut transaction)
+        .execute::<Task>()
+        .await
+    {
+        error!("Failed to write claim for task {}: {}", candidate.task_id, e);
+        return Err((
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({"error": format!("Failed to claim task: {}", e)})),
+        ));
+    }
+
+    if let Err(e) = transaction.commit().await {
+        warn!(
+            "Lost the claim race for task {} to another worker: {}",
+            candidate.task_id, e
+        );
+        return Ok(None);
+    }
+
+    emit_event(
+        &state.pubsub_client,
+        "TaskClaimedEvent",
+        &serde_json::to_string(&TaskClaimedEvent {
+            task: Some(claimed.clone()),
+        })
+        .unwrap(),
+    )
+    .await?;
+
+    Ok(Some(claimed))
+}
+
+/// A worker calls this periodically while it owns a `Running` task so the
+/// reaper (see `crate::reaper`) doesn't mistake it for crashed and reclaim
+/// the task out from under it.
+pub async fn task_heartbeat(
+    State(state): State<ApiState>,
+    _worker: AuthenticatedWorker,
+    Json(params): Json<HeartbeatCommand>,
+) -> impl IntoResponse {
+    debug!("Heartbeat from worker {} for task {}", params.worker_id, params.task_id);
+
+    let parent_path = match state.firestore_client.parent_path("users", params.user_id.clone()) {
+        Ok(path) => path,
+        Err(e) => {
+            error!("Failed to get parent path: {}", e);
+            return Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": format!("Failed to get parent path: {}", e)})),
+            ));
+        }
+    };
+
+    let task = match state
+        .firestore_client
+        .fluent()
+        .select()
+        .by_id_in("tasks")
+        .parent(parent_path.clone())
+        .obj::<Task>()
+        .one(&params.task_id)
+        .await
+    {
+        Ok(Some(task)) => task,
+        Ok(None) => {
+            return Err((
+                StatusCode::NOT_FOUND,
+                Json(json!({"error": format!("Failed to find task: {}", params.task_id)})),
+            ));
+        }
+        Err(e) => {
+            error!("Database error: {}", e);
+            return Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": format!("Failed to get task: {}", e)})),
+            ));
+        }
+    };
+
+    if task.status != i32::from(TaskStatus::Running) || task.claimed_by.as_deref() != Some(params.worker_id.as_str()) {
+        return Err((
+            StatusCode::CONFLICT,
+            Json(json!({"error": "Task is not claimed by this worker"})),
+        ));
+    }
+
+    if let Err(e) = state
+        .firestore_client
+        .fluent()
+        .update()
+        .fields(paths!(Task::{last_heartbeat}))
+        .in_col("tasks")
+        .document_id(&params.task_id)
+        .parent(parent_path)
+        .object(&Task {
+            last_heartbeat: Some(prost_wkt_types::Timestamp {
+                seconds: chrono::Utc::now().timestamp(),
+                nanos: 0,
+            }),
+            ..task
+        })
+        .execute::<Task>()
+        .await
+    {
+        error!("Failed to record heartbeat for task {}: {}", params.task_id, e);
+        return Err((
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({"error": format!("Failed to record heartbeat: {}", e)})),
+        ));
+    }
+
+    Ok((StatusCode::OK, Json(json!({"status": "Heartbeat recorded"}))))
+}
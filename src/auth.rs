@@ -0,0 +1,168 @@
+//! Bearer-token authentication for the public API: JWTs issued by
+//! `sign_in` and checked on every handler that acts on a specific user's
+//! tasks.
+
+use axum::extract::{FromRequestParts, State};
+use axum::http::request::Parts;
+use axum::http::{header, StatusCode};
+use axum::response::IntoResponse;
+use axum::Json;
+use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::ApiState;
+
+type AuthError = (StatusCode, Json<Value>);
+
+/// Claims issued by `sign_in` and checked by the `AuthenticatedUser` extractor.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Claims {
+    /// The user id this token was issued for.
+    pub sub: String,
+    /// Set for tokens issued to trusted internal services, which may act on
+    /// behalf of any user rather than only `sub`.
+    #[serde(default)]
+    pub service_role: bool,
+    pub exp: i64,
+}
+
+/// An axum extractor that requires a valid `Authorization: Bearer <token>`
+/// header, decoding it into the caller's identity.
+#[derive(Debug, Clone)]
+pub struct AuthenticatedUser {
+    pub user_id: String,
+    pub service_role: bool,
+}
+
+impl AuthenticatedUser {
+    /// Confirms this caller may act on behalf of `user_id`: either it *is*
+    /// that user, or it holds a service role. Returns `403` otherwise.
+    pub fn authorize(&self, user_id: &str) -> Result<(), AuthError> {
+        if self.service_role || self.user_id == user_id {
+            Ok(())
+        } else {
+            Err((
+                StatusCode::FORBIDDEN,
+                Json(json!({"error": "Not authorized to act on behalf of this user"})),
+            ))
+        }
+    }
+}
+
+impl FromRequestParts<ApiState> for AuthenticatedUser {
+    type Rejection = AuthError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &ApiState) -> Result<Self, Self::Rejection> {
+        let header_value = parts
+            .headers
+            .get(header::AUTHORIZATION)
+            .and_then(|v| v.to_str().ok())
+            .ok_or_else(|| unauthorized("Missing Authorization header"))?;
+
+        let token = header_value
+            .strip_prefix("Bearer ")
+            .ok_or_else(|| unauthorized("Authorization header must be a Bearer token"))?;
+
+        let claims = decode::<Claims>(
+            token,
+            &DecodingKey::from_secret(state.jwt_secret.as_bytes()),
+            &Validation::default(),
+        )
+        .map_err(|e| unauthorized(&format!("Invalid token: {}", e)))?
+        .claims;
+
+        Ok(AuthenticatedUser {
+            user_id: claims.sub,
+            service_role: claims.service_role,
+        })
+    }
+}
+
+fn unauthorized(message: &str) -> AuthError {
+    (StatusCode::UNAUTHORIZED, Json(json!({"error": message})))
+}
+
+/// An axum extractor for the worker pool: dispatch/heartbeat/artifact
+/// endpoints are called by trusted worker processes acting across every
+/// user's queue, not by a single signed-in user, so they're authenticated
+/// against a shared key instead of a per-user JWT.
+#[derive(Debug, Clone, Copy)]
+pub struct AuthenticatedWorker;
+
+impl FromRequestParts<ApiState> for AuthenticatedWorker {
+    type Rejection = AuthError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &ApiState) -> Result<Self, Self::Rejection> {
+        let provided = parts
+            .headers
+            .get("x-worker-key")
+            .and_then(|v| v.to_str().ok())
+            .ok_or_else(|| unauthorized("Missing worker key"))?;
+
+        if constant_time_eq(provided, &state.worker_api_key) {
+            Ok(AuthenticatedWorker)
+        } else {
+            Err(unauthorized("Invalid worker key"))
+        }
+    }
+}
+
+/// Compares two strings without leaking their common prefix length through
+/// timing, the way a plain `==` on the raw header value would.
+fn constant_time_eq(a: &str, b: &str) -> bool {
+    let (a, b) = (a.as_bytes(), b.as_bytes());
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SignInCommand {
+    pub user_id: String,
+    pub password: String,
+}
+
+#[derive(Debug, Serialize)]
+struct SignInResponse {
+    token: String,
+}
+
+/// Validates a user's password and, on success, issues a bearer token
+/// scoped to that user for `ApiState::token_expiry`.
+pub async fn sign_in(
+    State(state): State<ApiState>,
+    Json(params): Json<SignInCommand>,
+) -> impl IntoResponse {
+    let valid = crate::api::users::verify_credentials(
+        &state.firestore_client,
+        &params.user_id,
+        &params.password,
+    )
+    .await?;
+
+    if !valid {
+        return Err(unauthorized("Invalid credentials"));
+    }
+
+    let claims = Claims {
+        sub: params.user_id,
+        service_role: false,
+        exp: (chrono::Utc::now() + state.token_expiry).timestamp(),
+    };
+
+    let token = encode(
+        &Header::default(),
+        &claims,
+        &EncodingKey::from_secret(state.jwt_secret.as_bytes()),
+    )
+    .map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({"error": format!("Failed to sign token: {}", e)})),
+        )
+    })?;
+
+    Ok((StatusCode::OK, Json(SignInResponse { token })))
+}
@@ -0,0 +1,47 @@
+use serde::Deserialize;
+
+use crate::models::TaskData;
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct CreateTaskCommand {
+    pub task_id: String,
+    pub user_id: String,
+    pub task_data: Option<TaskData>,
+    pub object_path: Option<String>,
+    /// Number of attempts allowed before a `Failed` completion is terminal.
+    /// Defaults to `DEFAULT_MAX_ATTEMPTS` when omitted.
+    pub max_attempts: Option<i32>,
+    /// Base of the exponential retry backoff, in seconds. Defaults to
+    /// `DEFAULT_BACKOFF_BASE_SECONDS` when omitted.
+    pub backoff_base_seconds: Option<i64>,
+}
+
+/// Default retry policy applied when a caller doesn't specify one.
+pub const DEFAULT_MAX_ATTEMPTS: i32 = 3;
+pub const DEFAULT_BACKOFF_BASE_SECONDS: i64 = 5;
+
+/// Hard ceiling on `max_attempts`, regardless of what a caller requests.
+/// `backoff_base_seconds * 2^attempts` is only ever evaluated up to this
+/// many attempts, so this also bounds that computation.
+pub const MAX_ALLOWED_ATTEMPTS: i32 = 20;
+
+/// Hard ceiling on `backoff_base_seconds`, regardless of what a caller
+/// requests, so the retry delay computation stays well within `i64` even
+/// before the final `MAX_BACKOFF_SECONDS` cap is applied.
+pub const MAX_ALLOWED_BACKOFF_BASE_SECONDS: i64 = 3600;
+
+/// Body of a `claim_task` request: a worker advertising what it can run.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ClaimTaskCommand {
+    pub worker_id: String,
+    #[serde(default)]
+    pub capabilities: Vec<String>,
+}
+
+/// Body of a `task_heartbeat` request: a worker proving it's still alive.
+#[derive(Debug, Clone, Deserialize)]
+pub struct HeartbeatCommand {
+    pub task_id: String,
+    pub user_id: String,
+    pub worker_id: String,
+}
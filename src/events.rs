@@ -0,0 +1,39 @@
+use serde::{Deserialize, Serialize};
+
+use crate::models::{ArtifactRef, Task};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UserInfo {
+    pub user_id: String,
+    /// Plaintext password supplied at sign-up; hashed before it ever
+    /// touches Firestore, see `api::users::create_user`.
+    pub password: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UserCreatedEvent {
+    pub user: Option<UserInfo>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskCreatedEvent {
+    pub task: Option<Task>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskCompletedEvent {
+    pub task_id: String,
+    pub user_id: String,
+    pub status: i32,
+    pub result: Option<String>,
+    /// Manifest of uploaded outputs, filled in from the task record when
+    /// this event is emitted so downstream consumers can fetch them.
+    #[serde(default)]
+    pub artifacts: Vec<ArtifactRef>,
+}
+
+/// Announced once a worker has successfully claimed a `Queued` task.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskClaimedEvent {
+    pub task: Option<Task>,
+}
@@ -0,0 +1,36 @@
+pub mod api;
+pub mod auth;
+pub mod commands;
+pub mod events;
+pub mod models;
+pub mod reaper;
+
+use std::sync::Arc;
+
+use firestore::FirestoreDb;
+use google_cloud_pubsub::client::Client as PubsubClient;
+
+/// Shared state handed to every axum handler via `State<ApiState>`.
+#[derive(Clone)]
+pub struct ApiState {
+    pub firestore_client: FirestoreDb,
+    pub pubsub_client: PubsubClient,
+    /// How long a `Running` task may go without a heartbeat before the
+    /// reaper (see [`reaper::spawn`]) reclaims it back to `Queued`.
+    pub heartbeat_timeout: chrono::Duration,
+    /// Handle to the background reaper task spawned at startup (see
+    /// [`reaper::spawn`]).
+    pub reaper_handle: Arc<tokio::task::JoinHandle<()>>,
+    /// Shared secret used to sign and verify JWTs issued by `auth::sign_in`.
+    pub jwt_secret: Arc<str>,
+    /// Validity period of a freshly issued token.
+    pub token_expiry: chrono::Duration,
+    /// Shared key the worker pool presents to prove it's a trusted worker
+    /// rather than an arbitrary caller, checked by `auth::AuthenticatedWorker`.
+    /// Workers dispatch and report on tasks across every user's queue, so
+    /// they're authenticated as infrastructure, not as a signed-in user.
+    pub worker_api_key: Arc<str>,
+    /// Client for the GCS bucket artifacts are uploaded to/downloaded from.
+    pub gcs_client: google_cloud_storage::client::Client,
+    pub gcs_bucket: Arc<str>,
+}
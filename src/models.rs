@@ -0,0 +1,118 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// The lifecycle of a [`Task`] as it moves from creation through completion.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[repr(i32)]
+pub enum TaskStatus {
+    #[default]
+    Unspecified = 0,
+    Queued = 1,
+    Running = 2,
+    Completed = 3,
+    Failed = 4,
+    Cancelled = 5,
+    /// Failed with attempts remaining; waiting on `next_run_at` before the
+    /// reaper promotes it back to `Queued`.
+    Retrying = 6,
+}
+
+impl From<TaskStatus> for i32 {
+    fn from(status: TaskStatus) -> Self {
+        status as i32
+    }
+}
+
+impl TaskStatus {
+    /// Whether moving from `self` to `next` is a legal transition:
+    /// `Queued -> {Running, Cancelled}`, `Running -> {Completed, Failed,
+    /// Cancelled, Retrying}` and `Retrying -> Queued`. Terminal states
+    /// reject any further transition, including into themselves.
+    pub fn can_transition_to(&self, next: TaskStatus) -> bool {
+        use TaskStatus::*;
+        matches!(
+            (self, next),
+            (Queued, Running)
+                | (Queued, Cancelled)
+                | (Running, Completed)
+                | (Running, Failed)
+                | (Running, Cancelled)
+                | (Running, Retrying)
+                | (Retrying, Queued)
+        )
+    }
+}
+
+impl TryFrom<i32> for TaskStatus {
+    type Error = String;
+
+    fn try_from(value: i32) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(TaskStatus::Unspecified),
+            1 => Ok(TaskStatus::Queued),
+            2 => Ok(TaskStatus::Running),
+            3 => Ok(TaskStatus::Completed),
+            4 => Ok(TaskStatus::Failed),
+            5 => Ok(TaskStatus::Cancelled),
+            6 => Ok(TaskStatus::Retrying),
+            other => Err(format!("Unknown task status: {}", other)),
+        }
+    }
+}
+
+/// Reference to a binary artifact a worker uploaded for a task, stored in
+/// GCS rather than inline in the Firestore document.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ArtifactRef {
+    pub name: String,
+    pub size: u64,
+    pub content_type: String,
+    pub gcs_path: String,
+}
+
+/// Free-form parameters a caller attaches to a task at creation time.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TaskData {
+    #[serde(default)]
+    pub params: HashMap<String, String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Task {
+    pub task_id: String,
+    pub data: Option<TaskData>,
+    pub object_path: Option<String>,
+    pub created_by: String,
+    pub status: i32,
+    pub result: Option<String>,
+    pub duration_seconds: i64,
+
+    /// Worker id that currently owns this task while it is `Running`.
+    pub claimed_by: Option<String>,
+    pub claimed_at: Option<prost_wkt_types::Timestamp>,
+    /// Last time the claiming worker called `task_heartbeat`. Used by the
+    /// reaper to detect a worker that crashed mid-task.
+    pub last_heartbeat: Option<prost_wkt_types::Timestamp>,
+    /// Number of times this task has started and not finished cleanly.
+    /// Bumped both when the reaper reclaims it from a dead worker and when
+    /// a worker reports it `Failed` and a retry is scheduled; checked
+    /// against `max_attempts` either way.
+    pub attempts: i32,
+
+    /// Binary outputs uploaded for this task via `upload_artifact`.
+    #[serde(default)]
+    pub artifacts: Vec<ArtifactRef>,
+
+    /// Maximum number of attempts before a `Failed` completion is terminal
+    /// instead of being retried.
+    pub max_attempts: i32,
+    /// Base of the `backoff_base_seconds * 2^attempts` retry delay.
+    pub backoff_base_seconds: i64,
+    /// Earliest time the reaper may promote a `Retrying` task back to
+    /// `Queued`.
+    pub next_run_at: Option<prost_wkt_types::Timestamp>,
+
+    pub created_at: Option<prost_wkt_types::Timestamp>,
+    pub updated_at: Option<prost_wkt_types::Timestamp>,
+    pub last_publish_time: Option<prost_wkt_types::Timestamp>,
+}
@@ -0,0 +1,247 @@
+//! Background sweeps over Firestore: reclaiming `Running` tasks whose
+//! worker went away before calling `task_complete`, and promoting
+//! `Retrying` tasks back to `Queued` once their backoff has elapsed.
+
+use std::time::Duration;
+
+use firestore::{path, paths};
+use log::{error, info, warn};
+
+use crate::api::emit_event;
+use crate::events::TaskCreatedEvent;
+use crate::models::{Task, TaskStatus};
+use crate::ApiState;
+
+/// How often the reaper scans for stale `Running` tasks.
+const REAPER_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Spawns the reaper as a detached background task. The returned handle is
+/// kept around in `ApiState`; the task keeps running whether or not the
+/// handle is held.
+pub fn spawn(state: ApiState) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(REAPER_INTERVAL);
+        loop {
+            ticker.tick().await;
+            match reap_stale_tasks(&state).await {
+                Ok(0) => {}
+                Ok(reaped) => info!("Reaper reclaimed {} stale task(s)", reaped),
+                Err(e) => error!("Reaper sweep failed: {}", e),
+            }
+            match promote_retrying_tasks(&state).await {
+                Ok(0) => {}
+                Ok(promoted) => info!("Reaper promoted {} retrying task(s) back to Queued", promoted),
+                Err(e) => error!("Reaper retry sweep failed: {}", e),
+            }
+        }
+    })
+}
+
+/// Re-reads `task_id` inside a Firestore transaction and, only if it is
+/// still in `expected_status`, applies `mutate` and writes it back. Mirrors
+/// `claim_in_transaction` in `api::workers`: both guard against a concurrent
+/// writer (here, a worker calling `task_complete`) moving the document out
+/// from under a blind update. Returns `Ok(None)` if the precondition no
+/// longer holds or another writer won the race.
+async fn transition_in_transaction(
+    state: &ApiState,
+    task_id: &str,
+    created_by: &str,
+    expected_status: TaskStatus,
+    fields: Vec<String>,
+    mutate: impl FnOnce(Task) -> Task,
+) -> Result<Option<Task>, String> {
+    let parent_path = state
+        .firestore_client
+        .parent_path("users", created_by)
+        .map_err(|e| format!("Failed to get parent path: {}", e))?;
+
+    let mut transaction = state
+        .firestore_client
+        .begin_transaction()
+        .await
+        .map_err(|e| format!("Failed to begin transaction: {}", e))?;
+
+    let current = state
+        .firestore_client
+        .fluent()
+        .select()
+        .by_id_in("tasks")
+        .parent(parent_path.clone())
+        .obj::<Task>()
+        .one(task_id)
+        .add_to_transaction(&mut transaction)
+        .await
+        .map_err(|e| format!("Failed to read task {} in transaction: {}", task_id, e))?;
+
+    let Some(current) = current else {
+        return Ok(None);
+    };
+
+    if current.status != i32::from(expected_status) {
+        // A worker already called task_complete (or another sweep already
+        // moved it) between our query and this transaction; leave it alone.
+        return Ok(None);
+    }
+
+    let updated = mutate(current);
+
+    state
+        .firestore_client
+        .fluent()
+        .update()
+        .fields(fields)
+        .in_col("tasks")
+        .document_id(task_id)
+        .parent(parent_path)
+        .object(&updated)
+        .add_to_transaction(&mut transaction)
+        .execute::<Task>()
+        .await
+        .map_err(|e| format!("Failed to write task {} in transaction: {}", task_id, e))?;
+
+    match transaction.commit().await {
+        Ok(_) => Ok(Some(updated)),
+        Err(e) => {
+            warn!("Lost the race transitioning task {}: {}", task_id, e);
+            Ok(None)
+        }
+    }
+}
+
+/// Finds `Running` tasks whose `last_heartbeat` is older than
+/// `heartbeat_timeout`, returns them to `Queued`, clears their claim, bumps
+/// `attempts`, and re-announces them so another worker can pick them up.
+/// Returns the number of tasks reaped, for the caller to log/report.
+async fn reap_stale_tasks(state: &ApiState) -> Result<u64, String> {
+    let cutoff = chrono::Utc::now() - state.heartbeat_timeout;
+
+    let stale = state
+        .firestore_client
+        .fluent()
+        .select()
+        .from("tasks")
+        .all_descendants()
+        .filter(|q| {
+            q.for_all([
+                q.field(path!(Task::status)).eq(i32::from(TaskStatus::Running)),
+                q.field(path!(Task::last_heartbeat)).less_than_or_equal(prost_wkt_types::Timestamp {
+                    seconds: cutoff.timestamp(),
+                    nanos: 0,
+                }),
+            ])
+        })
+        .obj::<Task>()
+        .query()
+        .await
+        .map_err(|e| format!("Failed to query stale tasks: {}", e))?;
+
+    let mut reaped = 0u64;
+    for task in stale {
+        let prior_attempts = task.attempts;
+        let reclaimed = transition_in_transaction(
+            state,
+            &task.task_id,
+            &task.created_by,
+            TaskStatus::Running,
+            paths!(Task::{status, claimed_by, claimed_at, attempts, updated_at}),
+            move |current| Task {
+                status: TaskStatus::Queued.into(),
+                claimed_by: None,
+                claimed_at: None,
+                attempts: prior_attempts + 1,
+                updated_at: Some(prost_wkt_types::Timestamp {
+                    seconds: chrono::Utc::now().timestamp(),
+                    nanos: 0,
+                }),
+                ..current
+            },
+        )
+        .await?;
+
+        let Some(reclaimed) = reclaimed else {
+            continue;
+        };
+
+        emit_event(
+            &state.pubsub_client,
+            "TaskCreatedEvent",
+            &serde_json::to_string(&TaskCreatedEvent {
+                task: Some(reclaimed),
+            })
+            .unwrap(),
+        )
+        .await
+        .map_err(|(status, body)| format!("Failed to re-announce task {}: {} {:?}", task.task_id, status, body))?;
+
+        reaped += 1;
+    }
+
+    Ok(reaped)
+}
+
+/// Finds `Retrying` tasks whose `next_run_at` has passed, moves them back
+/// to `Queued`, and re-announces them. Returns the number promoted.
+async fn promote_retrying_tasks(state: &ApiState) -> Result<u64, String> {
+    let now = chrono::Utc::now();
+
+    let due = state
+        .firestore_client
+        .fluent()
+        .select()
+        .from("tasks")
+        .all_descendants()
+        .filter(|q| {
+            q.for_all([
+                q.field(path!(Task::status)).eq(i32::from(TaskStatus::Retrying)),
+                q.field(path!(Task::next_run_at)).less_than_or_equal(prost_wkt_types::Timestamp {
+                    seconds: now.timestamp(),
+                    nanos: 0,
+                }),
+            ])
+        })
+        .obj::<Task>()
+        .query()
+        .await
+        .map_err(|e| format!("Failed to query retrying tasks: {}", e))?;
+
+    let mut promoted = 0u64;
+    for task in due {
+        let requeued = transition_in_transaction(
+            state,
+            &task.task_id,
+            &task.created_by,
+            TaskStatus::Retrying,
+            paths!(Task::{status, next_run_at, updated_at}),
+            |current| Task {
+                status: TaskStatus::Queued.into(),
+                next_run_at: None,
+                updated_at: Some(prost_wkt_types::Timestamp {
+                    seconds: chrono::Utc::now().timestamp(),
+                    nanos: 0,
+                }),
+                ..current
+            },
+        )
+        .await?;
+
+        let Some(requeued) = requeued else {
+            continue;
+        };
+
+        emit_event(
+            &state.pubsub_client,
+            "TaskCreatedEvent",
+            &serde_json::to_string(&TaskCreatedEvent {
+                task: Some(requeued),
+            })
+            .unwrap(),
+        )
+        .await
+        .map_err(|(status, body)| format!("Failed to re-announce task {}: {} {:?}", task.task_id, status, body))?;
+
+        promoted += 1;
+    }
+
+    Ok(promoted)
+}